@@ -5,11 +5,11 @@ use d2simrs::environment::Environment;
 use d2simrs::util::uid::UIdGenRandom;
 use d2simrs::util::uid::UniqueId;
 use d2simrs::component::{ComponentBuilder, Component, ComponentBase, ChannelLabel};
+use d2simrs::context::{ExecutionContext, SendContext, TimeContext, TimerContext};
 use d2simrs::keys::{ComponentId, ChannelId};
-use d2simrs::scheduler::{Scheduler, NO_DELTA, SimTimeDelta};
+use d2simrs::scheduler::{NO_DELTA, SimTimeDelta};
 use d2simrs::channel::ChannelBuilder;
 use d2simrs::channels::delay_channel::DelayChannel;
-use rand::Rng;
 use std::fmt;
 
 // random delay channel builder ----------
@@ -23,11 +23,14 @@ impl Default for RandomDelayChannelBuilder {
     }
 }
 
+/// Draws each channel's delay from the seeded `Environment` RNG passed into
+/// `build_channel`, so channel delays are reproducible across runs like
+/// everything else keyed off the same seed.
 impl ChannelBuilder for RandomDelayChannelBuilder {
     type C = DelayChannel;
 
-    fn build_channel(&self, id: ChannelId, left: ComponentId, right: ComponentId) -> Self::C {
-        let delay_ms = rand::thread_rng().gen_range(1..11);
+    fn build_channel(&self, id: ChannelId, left: ComponentId, right: ComponentId, env: &mut Environment) -> Self::C {
+        let delay_ms = env.gen_range(1..11);
         let delay = std::time::Duration::from_millis(delay_ms);
 
         DelayChannel { id, left, right, delay: SimTimeDelta::from_duration(delay)}
@@ -48,7 +51,7 @@ impl ProcessBuilder {
 
 impl ComponentBuilder for ProcessBuilder {
 
-    fn build_component(&mut self, pid: ComponentId, _env: &mut Environment) -> Box<dyn Component> {
+    fn build_component(&mut self, pid: ComponentId, env: &mut Environment) -> Box<dyn Component> {
         let state;
 
         if self.has_root {
@@ -60,7 +63,7 @@ impl ComponentBuilder for ProcessBuilder {
 
         Box::new( Process {
             base: ComponentBase::new(pid),
-            uid: self.uid_gen.generate_uid(),
+            uid: self.uid_gen.generate_uid(env),
             state
         })
     }
@@ -95,45 +98,46 @@ impl Component for Process {
         return &mut self.base;
     }
 
-    fn init(&mut self, scheduler: &mut Scheduler) {
+    fn init(&mut self, ctx: &mut dyn ExecutionContext, _env: &mut Environment) {
         println!{"initialized process {:?}", self}
 
         if let State::Root = self.state {
-            scheduler.sched_self_event(NO_DELTA, self.sim_id());
+            ctx.sched_self_event(NO_DELTA, self.sim_id());
         }
     }
 
-    fn process_event(&mut self, sender: ComponentId, _event: Box<dyn Any>, scheduler: &mut Scheduler) {
+    fn process_event(&mut self, sender: ComponentId, _event: Box<dyn Any>, ctx: &mut dyn ExecutionContext, _env: &mut Environment) {
         assert_eq!(sender, self.sim_id());
-        println!("[time {}ms] starting process {:?}", scheduler.get_curr_time().as_millis(), self);
+        println!("[time {}ms] starting process {:?}", ctx.get_curr_time().as_millis(), self);
 
         for channel in &self.base.channels {
             let msg = Box::new(Message::new(
                 self.uid, self.uid
             ));
             println!{"\t sending message {:?} on channel {:?}", msg, channel}
-            scheduler.send_msg(self.sim_id(), *channel, msg);
+            ctx.send_msg(self.sim_id(), *channel, msg);
         }
     }
 
     fn receive_msg(&mut self,
-                   incoming_channel: ChannelId,
+                   incoming_channel: Option<ChannelId>,
                    msg: Box<dyn Any>,
-                   scheduler: &mut Scheduler
+                   ctx: &mut dyn ExecutionContext,
+                   _env: &mut Environment
     ) {
         let msg = msg.downcast::<Message>().unwrap();
         println!{"[time {}ms] process {} received msg {:?} on channel {:?}",
-                 scheduler.get_curr_time().as_millis(), self, msg, incoming_channel};
+                 ctx.get_curr_time().as_millis(), self, msg, incoming_channel};
 
         match &self.state {
             State::Unmarked => {
                 for channel in &self.base.channels {
-                    if incoming_channel != *channel {
+                    if incoming_channel != Some(*channel) {
                         let my_msg = Box::new(Message::new(
                             msg.root, self.uid
                         ));
                         println!{"\t sending message {:?} on channel {:?}", msg, channel}
-                        scheduler.send_msg(self.sim_id(), *channel, my_msg);
+                        ctx.send_msg(self.sim_id(), *channel, my_msg);
                     }
                 }
 
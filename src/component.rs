@@ -0,0 +1,41 @@
+use crate::context::ExecutionContext;
+use crate::environment::Environment;
+use crate::keys::{ChannelId, ComponentId};
+use std::any::Any;
+use std::fmt::Debug;
+
+#[derive(Debug)]
+pub struct ComponentBase {
+    pub id: ComponentId,
+    pub channels: Vec<ChannelId>,
+}
+
+impl ComponentBase {
+    pub fn new(id: ComponentId) -> Self {
+        ComponentBase { id, channels: Vec::new() }
+    }
+}
+
+pub trait Component: Debug {
+    fn get_sim_base(&self) -> &ComponentBase;
+    fn get_sim_base_mut(&mut self) -> &mut ComponentBase;
+
+    fn sim_id(&self) -> ComponentId {
+        self.get_sim_base().id
+    }
+
+    fn add_channel(&mut self, channel: ChannelId) {
+        self.get_sim_base_mut().channels.push(channel);
+    }
+
+    fn init(&mut self, ctx: &mut dyn ExecutionContext, env: &mut Environment);
+    fn process_event(&mut self, sender: ComponentId, event: Box<dyn Any>, ctx: &mut dyn ExecutionContext, env: &mut Environment);
+    /// `channel` is `None` when `msg` arrived via `Scheduler::publish` rather than
+    /// a point-to-point channel.
+    fn receive_msg(&mut self, channel: Option<ChannelId>, msg: Box<dyn Any>, ctx: &mut dyn ExecutionContext, env: &mut Environment);
+    fn terminate(&mut self, env: &mut Environment);
+}
+
+pub trait ComponentBuilder {
+    fn build_component(&mut self, id: ComponentId, env: &mut Environment) -> Box<dyn Component>;
+}
@@ -0,0 +1,176 @@
+//! Execution-context traits that decouple components from the concrete `Scheduler`.
+//!
+//! A `Component` written against `TimerContext + SendContext + TimeContext` can be
+//! driven either by a real `Scheduler` or by `MockContext` in a unit test, without
+//! standing up a whole `Simulation`.
+
+use crate::keys::{ChannelId, ComponentId};
+use crate::scheduler::{EventId, Scheduler, SimTime, SimTimeDelta};
+use std::any::Any;
+
+pub trait TimeContext {
+    fn get_curr_time(&self) -> &SimTime;
+}
+
+pub trait TimerContext {
+    fn sched_self_event(&mut self, timedelta: SimTimeDelta, process: ComponentId) -> EventId;
+    fn sched_receive_msg(&mut self, timedelta: SimTimeDelta, receiver: ComponentId, channel: Option<ChannelId>, message: Box<dyn Any>) -> EventId;
+    fn cancel(&mut self, id: EventId);
+    fn reschedule_self_event(&mut self, id: EventId, process: ComponentId, new_delta: SimTimeDelta) -> EventId;
+}
+
+pub trait SendContext {
+    fn send_msg(&mut self, sender: ComponentId, channel: ChannelId, message: Box<dyn Any>) -> EventId;
+    fn send_msg_delayed(&mut self, timedelta: SimTimeDelta, sender: ComponentId, channel: ChannelId, message: Box<dyn Any>) -> EventId;
+}
+
+impl TimeContext for Scheduler {
+    fn get_curr_time(&self) -> &SimTime {
+        Scheduler::get_curr_time(self)
+    }
+}
+
+impl TimerContext for Scheduler {
+    fn sched_self_event(&mut self, timedelta: SimTimeDelta, process: ComponentId) -> EventId {
+        Scheduler::sched_self_event(self, timedelta, process)
+    }
+
+    fn sched_receive_msg(&mut self, timedelta: SimTimeDelta, receiver: ComponentId, channel: Option<ChannelId>, message: Box<dyn Any>) -> EventId {
+        Scheduler::sched_receive_msg(self, timedelta, receiver, channel, message)
+    }
+
+    fn cancel(&mut self, id: EventId) {
+        Scheduler::cancel_event(self, id)
+    }
+
+    fn reschedule_self_event(&mut self, id: EventId, process: ComponentId, new_delta: SimTimeDelta) -> EventId {
+        Scheduler::reschedule_self_event(self, id, process, new_delta)
+    }
+}
+
+impl SendContext for Scheduler {
+    fn send_msg(&mut self, sender: ComponentId, channel: ChannelId, message: Box<dyn Any>) -> EventId {
+        Scheduler::send_msg(self, sender, channel, message)
+    }
+
+    fn send_msg_delayed(&mut self, timedelta: SimTimeDelta, sender: ComponentId, channel: ChannelId, message: Box<dyn Any>) -> EventId {
+        Scheduler::send_msg_delayed(self, timedelta, sender, channel, message)
+    }
+}
+
+/// Combines the three contexts so `Component` methods can take one `&mut dyn`
+/// argument instead of three, satisfied by both `Scheduler` and `MockContext`.
+pub trait ExecutionContext: TimerContext + SendContext + TimeContext {}
+impl<C: TimerContext + SendContext + TimeContext> ExecutionContext for C {}
+
+/// A self-event or outgoing message recorded by `MockContext`, for asserting on
+/// exactly what a component emitted without running a real simulation.
+#[derive(Debug)]
+pub enum RecordedAction {
+    SelfEvent { id: EventId, delta: SimTimeDelta, process: ComponentId },
+    ReceiveMsg { id: EventId, delta: SimTimeDelta, receiver: ComponentId, channel: Option<ChannelId> },
+    SendMsg { id: EventId, delta: SimTimeDelta, sender: ComponentId, channel: ChannelId },
+    Cancel { id: EventId },
+}
+
+/// A scripted stand-in for `Scheduler`: reports a fixed `curr_time` and
+/// records every timer/send call a component makes, instead of actually scheduling it.
+#[derive(Default)]
+pub struct MockContext {
+    curr_time: SimTime,
+    next_event_id: EventId,
+    pub actions: Vec<RecordedAction>,
+}
+
+impl MockContext {
+    pub fn new(curr_time: SimTime) -> Self {
+        MockContext { curr_time, next_event_id: 0, actions: Vec::new() }
+    }
+
+    fn next_id(&mut self) -> EventId {
+        let id = self.next_event_id;
+        self.next_event_id += 1;
+        id
+    }
+}
+
+impl TimeContext for MockContext {
+    fn get_curr_time(&self) -> &SimTime {
+        &self.curr_time
+    }
+}
+
+impl TimerContext for MockContext {
+    fn sched_self_event(&mut self, delta: SimTimeDelta, process: ComponentId) -> EventId {
+        let id = self.next_id();
+        self.actions.push(RecordedAction::SelfEvent { id, delta, process });
+        id
+    }
+
+    fn sched_receive_msg(&mut self, delta: SimTimeDelta, receiver: ComponentId, channel: Option<ChannelId>, _message: Box<dyn Any>) -> EventId {
+        let id = self.next_id();
+        self.actions.push(RecordedAction::ReceiveMsg { id, delta, receiver, channel });
+        id
+    }
+
+    fn cancel(&mut self, id: EventId) {
+        self.actions.push(RecordedAction::Cancel { id });
+    }
+
+    fn reschedule_self_event(&mut self, id: EventId, process: ComponentId, new_delta: SimTimeDelta) -> EventId {
+        self.cancel(id);
+        self.sched_self_event(new_delta, process)
+    }
+}
+
+impl SendContext for MockContext {
+    fn send_msg(&mut self, sender: ComponentId, channel: ChannelId, message: Box<dyn Any>) -> EventId {
+        self.send_msg_delayed(crate::scheduler::NO_DELTA, sender, channel, message)
+    }
+
+    fn send_msg_delayed(&mut self, delta: SimTimeDelta, sender: ComponentId, channel: ChannelId, _message: Box<dyn Any>) -> EventId {
+        let id = self.next_id();
+        self.actions.push(RecordedAction::SendMsg { id, delta, sender, channel });
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::component::{Component, ComponentBase};
+    use crate::environment::Environment;
+
+    #[derive(Debug)]
+    struct Echo { base: ComponentBase }
+
+    impl Component for Echo {
+        fn get_sim_base(&self) -> &ComponentBase { &self.base }
+        fn get_sim_base_mut(&mut self) -> &mut ComponentBase { &mut self.base }
+
+        fn init(&mut self, _ctx: &mut dyn ExecutionContext, _env: &mut Environment) {}
+
+        fn process_event(&mut self, _sender: ComponentId, _event: Box<dyn Any>, _ctx: &mut dyn ExecutionContext, _env: &mut Environment) {}
+
+        fn receive_msg(&mut self, channel: Option<ChannelId>, _msg: Box<dyn Any>, ctx: &mut dyn ExecutionContext, _env: &mut Environment) {
+            ctx.send_msg(self.sim_id(), channel.expect("Echo only wired to point-to-point channels"), Box::new(()));
+        }
+
+        fn terminate(&mut self, _env: &mut Environment) {}
+    }
+
+    #[test]
+    fn mock_context_records_emitted_send() {
+        let mut echo = Echo { base: ComponentBase::new(1) };
+        let mut ctx = MockContext::default();
+        let mut env = Environment::default();
+
+        echo.receive_msg(Some(7), Box::new(()), &mut ctx, &mut env);
+
+        assert_eq!(ctx.actions.len(), 1);
+        match &ctx.actions[0] {
+            RecordedAction::SendMsg { channel, .. } => assert_eq!(*channel, 7),
+            other => panic!("expected a recorded send, got {:?}", other),
+        }
+    }
+}
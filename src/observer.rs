@@ -0,0 +1,53 @@
+use crate::scheduler::{EventId, EventType, SimTime};
+use std::any::Any;
+
+pub trait SimObserver: Any {
+    fn on_schedule(&mut self, seqnum: EventId, time: SimTime, event: &EventType);
+    fn on_dispatch(&mut self, seqnum: EventId, cause: Option<EventId>, time: SimTime, event: &EventType);
+
+    /// Lets callers downcast the `&dyn SimObserver` they get back from
+    /// `Scheduler::observer`/`Simulation::observer` to their concrete type, e.g.
+    /// to call `CausalityTracer::dump` after a run.
+    fn as_any(&self) -> &dyn Any;
+}
+
+/// Does nothing, preserving the previous silent behavior.
+#[derive(Default)]
+pub struct NoopObserver;
+
+impl SimObserver for NoopObserver {
+    fn on_schedule(&mut self, _seqnum: EventId, _time: SimTime, _event: &EventType) {}
+    fn on_dispatch(&mut self, _seqnum: EventId, _cause: Option<EventId>, _time: SimTime, _event: &EventType) {}
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Records the parent -> child event DAG (by seqnum).
+#[derive(Default)]
+pub struct CausalityTracer {
+    edges: Vec<(Option<EventId>, EventId, SimTime)>,
+}
+
+impl CausalityTracer {
+    pub fn new() -> Self {
+        CausalityTracer { edges: Vec::new() }
+    }
+
+    pub fn dump(&self) -> &[(Option<EventId>, EventId, SimTime)] {
+        &self.edges
+    }
+}
+
+impl SimObserver for CausalityTracer {
+    fn on_schedule(&mut self, _seqnum: EventId, _time: SimTime, _event: &EventType) {}
+
+    fn on_dispatch(&mut self, seqnum: EventId, cause: Option<EventId>, time: SimTime, _event: &EventType) {
+        self.edges.push((cause, seqnum, time));
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
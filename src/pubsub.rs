@@ -0,0 +1,54 @@
+use crate::keys::ComponentId;
+use std::any::TypeId;
+use std::collections::HashMap;
+
+/// Topic-based publish/subscribe registry; `Scheduler::publish` delivers to its
+/// subscribers through `receive_msg`, without going through `send_msg` or a channel.
+#[derive(Default)]
+pub struct PubSub {
+    subscribers: HashMap<(String, TypeId), Vec<ComponentId>>,
+}
+
+impl PubSub {
+    pub fn subscribe<T: 'static>(&mut self, topic: &str, component: ComponentId) {
+        self.subscribers.entry((topic.to_string(), TypeId::of::<T>())).or_default().push(component);
+    }
+
+    pub fn subscribers<T: 'static>(&self, topic: &str) -> &[ComponentId] {
+        self.subscribers.get(&(topic.to_string(), TypeId::of::<T>()))
+            .map(|v| v.as_slice())
+            .unwrap_or(&[])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subscribers_returns_only_those_subscribed_to_the_topic() {
+        let mut pubsub = PubSub::default();
+        pubsub.subscribe::<u32>("topic-a", 1);
+        pubsub.subscribe::<u32>("topic-a", 2);
+        pubsub.subscribe::<u32>("topic-b", 3);
+
+        assert_eq!(pubsub.subscribers::<u32>("topic-a"), &[1, 2]);
+        assert_eq!(pubsub.subscribers::<u32>("topic-b"), &[3]);
+    }
+
+    #[test]
+    fn subscribers_are_isolated_by_message_type_on_the_same_topic() {
+        let mut pubsub = PubSub::default();
+        pubsub.subscribe::<u32>("topic", 1);
+        pubsub.subscribe::<String>("topic", 2);
+
+        assert_eq!(pubsub.subscribers::<u32>("topic"), &[1]);
+        assert_eq!(pubsub.subscribers::<String>("topic"), &[2]);
+    }
+
+    #[test]
+    fn unknown_topic_has_no_subscribers() {
+        let pubsub = PubSub::default();
+        assert_eq!(pubsub.subscribers::<u32>("nobody-subscribed"), &[] as &[ComponentId]);
+    }
+}
@@ -1,11 +1,16 @@
 use crate::keys::{ComponentId, ChannelId};
-use std::collections::BinaryHeap;
+use crate::observer::{NoopObserver, SimObserver};
+use std::collections::{BinaryHeap, HashSet};
 use std::any::Any;
 use std::cmp::Ordering;
 use std::cmp::PartialEq;
 use std::time::Duration;
 use crate::environment::Environment;
 
+/// Identifies a previously scheduled event, returned by the `sched_*`/`send_msg*`
+/// methods so it can later be cancelled or rescheduled.
+pub type EventId = u64;
+
 #[derive(Debug)]
 pub struct ProcessEvent {
     pub sender: ComponentId,
@@ -18,13 +23,27 @@ pub struct MessageSendEvent {
     pub sender: ComponentId,
     pub channel:ChannelId,
     pub message: Box<dyn Any>,
+    /// Seqnum of the event that was being dispatched when this send was issued.
+    pub cause: Option<EventId>,
 }
 
 #[derive(Debug)]
 pub struct MessageRcvEvent {
-    pub channel:ChannelId,
+    /// `None` for messages with no backing point-to-point channel, e.g. those
+    /// fanned out by `Scheduler::publish`.
+    pub channel: Option<ChannelId>,
     pub receiver: ComponentId,
     pub message: Box<dyn Any>,
+    /// Seqnum of the event that was being dispatched when this receive was scheduled.
+    pub cause: Option<EventId>,
+}
+
+pub(crate) fn cause_of(event: &EventType) -> Option<EventId> {
+    match event {
+        EventType::MsgSendEvent(e) => e.cause,
+        EventType::MsgRcvEvent(e) => e.cause,
+        EventType::ProcessEvent(_) | EventType::EndSimulation => None,
+    }
 }
 
 #[derive(Debug)]
@@ -88,6 +107,7 @@ impl SimTime {
 struct ScheduledEvent
 {
     time: SimTime,
+    id: EventId,
     event: EventType,
 }
 
@@ -122,74 +142,206 @@ pub struct Scheduler
     curr_time: SimTime,
     pub(crate) env: Environment,
     sim_status: SimStatus,
+    next_event_id: EventId,
+    /// Ids currently sitting in `events`, unfired and uncancelled.
+    pending: HashSet<EventId>,
+    cancelled: HashSet<EventId>,
+    /// Seqnum of the event currently being dispatched, stamped onto any
+    /// message scheduled while handling it, so causality can be traced back.
+    dispatching: Option<EventId>,
+    observer: Box<dyn SimObserver>,
 }
 
 impl Scheduler
 {
     pub fn new() -> Self {
-        Scheduler { events: BinaryHeap::default(), curr_time: SimTime::default(), env: Environment::default(), sim_status: SimStatus::Ok}
+        Scheduler {
+            events: BinaryHeap::default(),
+            curr_time: SimTime::default(),
+            env: Environment::default(),
+            sim_status: SimStatus::Ok,
+            next_event_id: 0,
+            pending: HashSet::new(),
+            cancelled: HashSet::new(),
+            dispatching: None,
+            observer: Box::new(NoopObserver),
+        }
     }
 
     pub fn get_curr_time(&self) -> &SimTime {
         return &self.curr_time;
     }
 
+    pub fn set_observer(&mut self, observer: Box<dyn SimObserver>) {
+        self.observer = observer;
+    }
+
+    /// Gives callers a handle back to the observer they installed, e.g. to
+    /// downcast to `CausalityTracer` and `dump()` it after a run.
+    pub fn observer(&self) -> &dyn SimObserver {
+        self.observer.as_ref()
+    }
+
+    fn next_id(&mut self) -> EventId {
+        let id = self.next_event_id;
+        self.next_event_id += 1;
+        id
+    }
+
     pub fn next_event(&mut self) -> EventType {
 
         if let SimStatus::Failure = self.sim_status {
             return EventType::EndSimulation;
         }
 
-        let event = self.events.pop();
+        loop {
+            let event = match self.events.pop() {
+                None => return EventType::EndSimulation,
+                Some(event) => event,
+            };
 
-        if event.is_none() {
-            return EventType::EndSimulation;
-        }
+            // updaate time
+            self.curr_time.advance_to (event.time);
 
-        let event = event.unwrap();
+            self.pending.remove(&event.id);
+            if self.cancelled.remove(&event.id) {
+                // lazily dropped: time has already advanced past it
+                continue;
+            }
 
-        // updaate time
-        self.curr_time.advance_to (event.time);
+            let cause = cause_of(&event.event);
+            self.observer.on_dispatch(event.id, cause, event.time, &event.event);
+            self.dispatching = Some(event.id);
 
-        return event.event;
+            return event.event;
+        }
     }
 
-    pub fn send_msg_delayed(&mut self, timedelta: SimTimeDelta, sender: ComponentId, channel: ChannelId, message: Box<dyn Any>) {
+    pub fn send_msg_delayed(&mut self, timedelta: SimTimeDelta, sender: ComponentId, channel: ChannelId, message: Box<dyn Any>) -> EventId {
         let time = self.curr_time + timedelta;
-        let event = ScheduledEvent { time, event: EventType::MsgSendEvent(
-            MessageSendEvent { sender, channel, message }
+        let id = self.next_id();
+        let event = ScheduledEvent { time, id, event: EventType::MsgSendEvent(
+            MessageSendEvent { sender, channel, message, cause: self.dispatching }
         )};
+        self.observer.on_schedule(id, event.time, &event.event);
         self.events.push(event);
+        self.pending.insert(id);
+        id
     }
 
-    pub fn send_msg(&mut self, sender: ComponentId, channel: ChannelId, message: Box<dyn Any>){
-        self.send_msg_delayed(NO_DELTA, sender, channel, message);
+    pub fn send_msg(&mut self, sender: ComponentId, channel: ChannelId, message: Box<dyn Any>) -> EventId {
+        self.send_msg_delayed(NO_DELTA, sender, channel, message)
     }
 
-    pub fn sched_receive_msg(&mut self, timedelta: SimTimeDelta, receiver: ComponentId, channel: ChannelId, message: Box<dyn Any>) {
+    pub fn sched_receive_msg(&mut self, timedelta: SimTimeDelta, receiver: ComponentId, channel: Option<ChannelId>, message: Box<dyn Any>) -> EventId {
         let time = self.curr_time + timedelta;
-        let event = ScheduledEvent { time, event: EventType::MsgRcvEvent(
-            MessageRcvEvent {channel, receiver, message}
+        let id = self.next_id();
+        let event = ScheduledEvent { time, id, event: EventType::MsgRcvEvent(
+            MessageRcvEvent {channel, receiver, message, cause: self.dispatching}
         )};
+        self.observer.on_schedule(id, event.time, &event.event);
         self.events.push(event);
+        self.pending.insert(id);
+        id
     }
 
-    pub fn sched_self_event(&mut self, timedelta: SimTimeDelta, process: ComponentId) {
-        assert!(self.curr_time.is_zero());
-
+    pub fn sched_self_event(&mut self, timedelta: SimTimeDelta, process: ComponentId) -> EventId {
         let time = self.curr_time + timedelta;
-        let event = ScheduledEvent { time, event: EventType::ProcessEvent(
+        let id = self.next_id();
+        let event = ScheduledEvent { time, id, event: EventType::ProcessEvent(
             ProcessEvent {
                 sender: process,
                 receiver: process,
                 event: Box::new(std::ptr::null::<usize>())
             }
         )};
-        // eprintln!("\t\t\tcreated event = {:?}", event);
+        self.observer.on_schedule(id, event.time, &event.event);
         self.events.push(event);
+        self.pending.insert(id);
+        id
+    }
+
+    /// Fans `message` out to every subscriber of `topic` registered in `env` (the
+    /// `Environment` components subscribed on) as a `MsgRcvEvent` with
+    /// `channel: None`, since pub/sub delivery has no single backing point-to-point
+    /// channel.
+    /// TODO: honor each subscriber's own channel delay once pub/sub can look up
+    /// which channel connects publisher and subscriber; delivery is instant for now.
+    pub fn publish<T: Clone + 'static>(&mut self, env: &Environment, topic: &str, message: T) -> Vec<EventId> {
+        let subscribers = env.pubsub.subscribers::<T>(topic).to_vec();
+        subscribers.into_iter()
+            .map(|receiver| self.sched_receive_msg(NO_DELTA, receiver, None, Box::new(message.clone())))
+            .collect()
+    }
+
+    /// Cancels a previously scheduled event. The event is only removed lazily:
+    /// when it is popped off `events`, `next_event` skips it instead of dispatching it.
+    /// A no-op if `id` already fired or was never pending, so repeated cancels of
+    /// a stale id don't grow `cancelled` without bound.
+    pub fn cancel_event(&mut self, id: EventId) {
+        if self.pending.remove(&id) {
+            self.cancelled.insert(id);
+        }
+    }
+
+    /// Cancels `id` and arms a fresh self-event for `process` after `new_delta`.
+    /// Only self-events can be rebuilt this way (there's no stored payload to
+    /// replay for a `MsgSendEvent`/`MsgRcvEvent`), hence the narrower name and
+    /// signature than a fully general `reschedule(id, new_delta)`.
+    pub fn reschedule_self_event(&mut self, id: EventId, process: ComponentId, new_delta: SimTimeDelta) -> EventId {
+        self.cancel_event(id);
+        self.sched_self_event(new_delta, process)
     }
 
     pub fn sim_error(&mut self) {
         self.sim_status = SimStatus::Failure;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancelled_event_is_skipped_without_dispatch() {
+        let mut s = Scheduler::new();
+        let a = s.sched_self_event(NO_DELTA, 1);
+        s.sched_self_event(ROUND_DELTA, 2);
+        s.cancel_event(a);
+
+        match s.next_event() {
+            EventType::ProcessEvent(pe) => assert_eq!(pe.sender, 2),
+            other => panic!("expected process event for process 2, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn cancelling_an_already_fired_id_is_a_noop() {
+        let mut s = Scheduler::new();
+        let a = s.sched_self_event(NO_DELTA, 1);
+        let _ = s.next_event();
+
+        s.cancel_event(a);
+
+        assert!(s.cancelled.is_empty());
+    }
+
+    #[test]
+    fn reschedule_self_event_replaces_the_original_timer() {
+        let mut s = Scheduler::new();
+        let a = s.sched_self_event(ROUND_DELTA, 1);
+        let new_delta = SimTimeDelta::from_duration(Duration::from_secs(2));
+        let b = s.reschedule_self_event(a, 1, new_delta);
+        assert_ne!(a, b);
+
+        match s.next_event() {
+            EventType::ProcessEvent(pe) => assert_eq!(pe.sender, 1),
+            other => panic!("expected process event for process 1, got {:?}", other),
+        }
+        assert_eq!(s.get_curr_time().as_rounds(), 2);
+
+        // the original timer at round 1 was cancelled by the reschedule, so the
+        // only event left to dispatch is EndSimulation.
+        assert!(matches!(s.next_event(), EventType::EndSimulation));
+    }
+}
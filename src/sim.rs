@@ -3,6 +3,7 @@ use crate::component::{Component, ComponentBuilder};
 use crate::channel::Channel;
 use crate::environment::Environment;
 use crate::keys::{ComponentId, ChannelId};
+use crate::observer::SimObserver;
 use std::collections::HashMap;
 
 static ID_COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(1);
@@ -35,6 +36,25 @@ impl Default for Simulation {
 
 impl Simulation {
 
+    /// Seeds the simulation's master RNG so channel delays, uid generation and
+    /// other randomized behavior are reproducible across runs.
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            components: HashMap::new(),
+            channels: HashMap::new(),
+            scheduler: RoundScheduler::new(),
+            env: Environment::with_seed(seed),
+        }
+    }
+
+    pub fn set_observer(&mut self, observer: Box<dyn SimObserver>) {
+        self.scheduler.set_observer(observer);
+    }
+
+    pub fn observer(&self) -> &dyn SimObserver {
+        self.scheduler.observer()
+    }
+
     pub fn add_process(&mut self,  builder: &mut dyn ComponentBuilder) -> ComponentId {
         let id = generate_next_id();
 
@@ -63,43 +83,26 @@ impl Simulation {
     }
 
     pub fn step(&mut self) -> bool {
-        // eprintln!("self.scheduler.events = {:?}", self.scheduler.events);
-        let event = self.scheduler.events.pop();
-
-        if event.is_none() {
-            return false;
-        }
-
-        let event = event.unwrap();
-
-        // updaate time
-        if self.scheduler.curr_time > event.time {
-            eprintln!("processing event = {:?}", event);
-            eprintln!("self.scheduler.events = {:?}", self.scheduler.events);
-            assert!(self.scheduler.curr_time <= event.time, "time mismatch: {} {}", self.scheduler.curr_time, event.time);
-        }
-
-        if self.scheduler.curr_time < event.time {
-            self.scheduler.curr_time = event.time;
-        }
-
-        match event.event {
+        // single dispatch loop lives in Scheduler::next_event so lazy-cancellation
+        // and observer notification aren't duplicated here
+        match self.scheduler.next_event() {
+            EventType::EndSimulation => false,
             EventType::ProcessEvent(ev_data) => {
                 let component = self.components.get_mut(&ev_data.receiver).unwrap();
                 component.process_event(ev_data.sender, ev_data.event, &mut self.scheduler, &mut self.env);
+                true
             },
             EventType::MsgSendEvent(ev_data) => {
                 let channel = self.channels.get_mut(&ev_data.channel).unwrap();
                 channel.message_from(ev_data.sender, ev_data.message, &mut self.scheduler);
+                true
             },
             EventType::MsgRcvEvent(ev_data) => {
-                // println!("event at time: {}", self.scheduler.curr_time);
                 let process = self.components.get_mut(&ev_data.receiver).unwrap();
                 process.receive_msg(ev_data.channel, ev_data.message, &mut self.scheduler, &mut self.env);
+                true
             }
         }
-
-        true
     }
 
     pub fn run(&mut self)  {
@@ -118,4 +121,76 @@ impl Simulation {
 
         assert!(validate(&self.components));
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::component::ComponentBase;
+    use crate::context::ExecutionContext;
+    use crate::scheduler::NO_DELTA;
+    use std::any::Any;
+
+    /// A component whose behavior depends on `env`'s RNG, so a simulation run's
+    /// final state is a regression check on seeded reproducibility end-to-end,
+    /// not just on `Environment::gen_range` in isolation.
+    struct RandomWalker {
+        base: ComponentBase,
+        draws: Vec<u32>,
+    }
+
+    // leaves out `base`: its id comes from the process-wide ID_COUNTER, so it
+    // differs between runs regardless of the seed
+    impl std::fmt::Debug for RandomWalker {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("RandomWalker").field("draws", &self.draws).finish()
+        }
+    }
+
+    impl Component for RandomWalker {
+        fn get_sim_base(&self) -> &ComponentBase { &self.base }
+        fn get_sim_base_mut(&mut self) -> &mut ComponentBase { &mut self.base }
+
+        fn init(&mut self, ctx: &mut dyn ExecutionContext, _env: &mut Environment) {
+            ctx.sched_self_event(NO_DELTA, self.sim_id());
+        }
+
+        fn process_event(&mut self, _sender: ComponentId, _event: Box<dyn Any>, ctx: &mut dyn ExecutionContext, env: &mut Environment) {
+            self.draws.push(env.gen_range(0..1_000_000));
+            if self.draws.len() < 5 {
+                ctx.sched_self_event(NO_DELTA, self.sim_id());
+            }
+        }
+
+        fn receive_msg(&mut self, _channel: Option<ChannelId>, _msg: Box<dyn Any>, _ctx: &mut dyn ExecutionContext, _env: &mut Environment) {}
+
+        fn terminate(&mut self, _env: &mut Environment) {}
+    }
+
+    struct RandomWalkerBuilder;
+
+    impl ComponentBuilder for RandomWalkerBuilder {
+        fn build_component(&mut self, id: ComponentId, _env: &mut Environment) -> Box<dyn Component> {
+            Box::new(RandomWalker { base: ComponentBase::new(id), draws: Vec::new() })
+        }
+    }
+
+    fn run_with_seed(seed: u64) -> String {
+        let mut sim = Simulation::with_seed(seed);
+        sim.add_process(&mut RandomWalkerBuilder);
+        sim.call_init();
+        sim.run();
+        // format the components only, not the map keys (their ids)
+        format!("{:?}", sim.components.values().collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn same_seed_yields_identical_final_component_state() {
+        assert_eq!(run_with_seed(7), run_with_seed(7));
+    }
+
+    #[test]
+    fn different_seeds_diverge() {
+        assert_ne!(run_with_seed(1), run_with_seed(2));
+    }
 }
\ No newline at end of file
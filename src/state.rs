@@ -0,0 +1,151 @@
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+use std::marker::PhantomData;
+
+/// A type-safe handle to a value previously inserted into `State`.
+#[derive(Debug)]
+pub struct Key<T> {
+    index: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Key<T> {
+    fn new(index: usize) -> Self {
+        Key { index, _marker: PhantomData }
+    }
+}
+
+impl<T> Clone for Key<T> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<T> Copy for Key<T> {}
+
+impl<T> PartialEq for Key<T> {
+    fn eq(&self, other: &Self) -> bool { self.index == other.index }
+}
+
+impl<T> Eq for Key<T> {}
+
+/// A type-safe handle to a FIFO queue previously created with `State::new_queue`.
+#[derive(Debug)]
+pub struct QueueId<T> {
+    index: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> QueueId<T> {
+    fn new(index: usize) -> Self {
+        QueueId { index, _marker: PhantomData }
+    }
+}
+
+impl<T> Clone for QueueId<T> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<T> Copy for QueueId<T> {}
+
+impl<T> PartialEq for QueueId<T> {
+    fn eq(&self, other: &Self) -> bool { self.index == other.index }
+}
+
+impl<T> Eq for QueueId<T> {}
+
+/// A generic, downcasting value store plus typed FIFO queues.
+///
+/// Values and queues share one id space, each boxed behind `dyn Any` and
+/// downcast back to `T` on access via the type-tagged `Key`/`QueueId`.
+#[derive(Default)]
+pub struct State {
+    values: HashMap<usize, Box<dyn Any>>,
+    queues: HashMap<usize, Box<dyn Any>>,
+    next_id: usize,
+}
+
+impl State {
+    fn alloc_id(&mut self) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    pub fn insert<T: 'static>(&mut self, value: T) -> Key<T> {
+        let id = self.alloc_id();
+        self.values.insert(id, Box::new(value));
+        Key::new(id)
+    }
+
+    pub fn get<T: 'static>(&self, key: Key<T>) -> Option<&T> {
+        self.values.get(&key.index).and_then(|v| v.downcast_ref::<T>())
+    }
+
+    pub fn get_mut<T: 'static>(&mut self, key: Key<T>) -> Option<&mut T> {
+        self.values.get_mut(&key.index).and_then(|v| v.downcast_mut::<T>())
+    }
+
+    pub fn remove<T: 'static>(&mut self, key: Key<T>) -> Option<T> {
+        self.values.remove(&key.index).and_then(|v| v.downcast::<T>().ok()).map(|v| *v)
+    }
+
+    pub fn new_queue<T: 'static>(&mut self) -> QueueId<T> {
+        let id = self.alloc_id();
+        self.queues.insert(id, Box::new(VecDeque::<T>::new()));
+        QueueId::new(id)
+    }
+
+    pub fn push<T: 'static>(&mut self, queue: QueueId<T>, value: T) {
+        let queue = self.queues.get_mut(&queue.index)
+            .and_then(|q| q.downcast_mut::<VecDeque<T>>())
+            .expect("unknown queue id");
+        queue.push_back(value);
+    }
+
+    pub fn pop<T: 'static>(&mut self, queue: QueueId<T>) -> Option<T> {
+        self.queues.get_mut(&queue.index)
+            .and_then(|q| q.downcast_mut::<VecDeque<T>>())
+            .and_then(|q| q.pop_front())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_get_get_mut_remove_round_trip() {
+        let mut state = State::default();
+        let key = state.insert(42i32);
+
+        assert_eq!(state.get(key), Some(&42));
+
+        *state.get_mut(key).unwrap() += 1;
+        assert_eq!(state.get(key), Some(&43));
+
+        assert_eq!(state.remove(key), Some(43));
+        assert_eq!(state.get(key), None);
+    }
+
+    #[test]
+    fn wrong_type_lookup_returns_none() {
+        let mut state = State::default();
+        let key = state.insert(42i32);
+
+        // same id space, wrong T: simulates a forged/mismatched Key
+        let wrong: Key<String> = Key::new(key.index);
+        assert_eq!(state.get(wrong), None);
+    }
+
+    #[test]
+    fn queue_push_pop_is_fifo_and_drains_to_none() {
+        let mut state = State::default();
+        let queue = state.new_queue::<&str>();
+
+        state.push(queue, "a");
+        state.push(queue, "b");
+
+        assert_eq!(state.pop(queue), Some("a"));
+        assert_eq!(state.pop(queue), Some("b"));
+        assert_eq!(state.pop(queue), None);
+    }
+}
@@ -0,0 +1,30 @@
+use crate::environment::Environment;
+use std::collections::HashSet;
+
+/// An id drawn from `UIdGenRandom`, unique among the ids it has handed out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UniqueId(usize);
+
+/// Hands out ids drawn from `0..max_uid`, retrying on collision so every draw
+/// within a run is unique.
+pub struct UIdGenRandom {
+    max_uid: usize,
+    used: HashSet<usize>,
+}
+
+impl UIdGenRandom {
+    pub fn new(max_uid: usize) -> Self {
+        UIdGenRandom { max_uid, used: HashSet::new() }
+    }
+
+    /// Draws from `env`'s seeded RNG so assignment is reproducible across runs
+    /// with the same seed.
+    pub fn generate_uid(&mut self, env: &mut Environment) -> UniqueId {
+        loop {
+            let candidate = env.gen_range(0..self.max_uid);
+            if self.used.insert(candidate) {
+                return UniqueId(candidate);
+            }
+        }
+    }
+}